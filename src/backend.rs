@@ -0,0 +1,151 @@
+//  fitr  --  GPX track analysis for the command line with rust
+//  Copyright (C) 2019 - Fabian A.J. Thiele, <fabian.thiele@posteo.de>
+//
+//  This file is part of fitr.
+//
+//  fitr is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  fitr is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Terminal backend selection, mirroring the multi-backend model tui itself
+// adopted: the `termion`/`crossterm`/`rustbox` Cargo features pick the
+// backend (termion by default; when several are enabled, termion wins over
+// crossterm, which wins over rustbox), and `run_prog` only ever sees the
+// small `TerminalSetup` trait below, never the concrete backend crate.
+
+use tui::backend::Backend as TuiBackend;
+use tui::Terminal;
+
+use crate::error::FitrError;
+use crate::util::{Config, Events, Key};
+
+/// Everything a concrete terminal implementation has to provide: how to
+/// enter raw/alternate-screen mode and build a `tui::Terminal`, plus the
+/// key event source `run_prog`'s main loop reads from.
+pub trait TerminalSetup {
+    type Backend: TuiBackend;
+
+    fn init(config: Config) -> Result<(Terminal<Self::Backend>, Events), FitrError>;
+}
+
+#[cfg(feature = "termion")]
+mod termion_backend {
+    use super::*;
+    use termion::event::Key as TermionKey;
+    use termion::input::{MouseTerminal, TermRead};
+    use termion::raw::IntoRawMode;
+    use termion::screen::AlternateScreen;
+    use tui::backend::TermionBackend;
+
+    fn to_key(key: TermionKey) -> Key {
+        match key {
+            TermionKey::Char(c) => Key::Char(c),
+            TermionKey::Up => Key::Up,
+            TermionKey::Down => Key::Down,
+            TermionKey::Left => Key::Left,
+            TermionKey::Right => Key::Right,
+            TermionKey::PageUp => Key::PageUp,
+            TermionKey::PageDown => Key::PageDown,
+            _ => Key::Other,
+        }
+    }
+
+    pub struct TermionSetup;
+
+    impl TerminalSetup for TermionSetup {
+        type Backend = TermionBackend<AlternateScreen<MouseTerminal<std::io::Stdout>>>;
+
+        fn init(config: Config) -> Result<(Terminal<Self::Backend>, Events), FitrError> {
+            let stdout = std::io::stdout().into_raw_mode().map_err(|e| FitrError::Terminal(Box::new(e)))?;
+            let stdout = MouseTerminal::from(stdout);
+            let stdout = AlternateScreen::from(stdout);
+            let backend = TermionBackend::new(stdout);
+            let mut terminal = Terminal::new(backend).map_err(|e| FitrError::Terminal(Box::new(e)))?;
+            terminal.hide_cursor().map_err(|e| FitrError::Terminal(Box::new(e)))?;
+
+            let keys = std::io::stdin().keys().filter_map(Result::ok).map(to_key);
+            Ok((terminal, Events::with_config(config, keys)))
+        }
+    }
+}
+#[cfg(feature = "termion")]
+pub use self::termion_backend::TermionSetup as ActiveBackend;
+
+#[cfg(feature = "crossterm")]
+mod crossterm_backend {
+    use super::*;
+    use crossterm::{input, AlternateScreen, InputEvent, KeyEvent, RawScreen};
+    use tui::backend::CrosstermBackend;
+
+    fn to_key(event: InputEvent) -> Option<Key> {
+        match event {
+            InputEvent::Keyboard(KeyEvent::Char(c)) => Some(Key::Char(c)),
+            InputEvent::Keyboard(KeyEvent::Up) => Some(Key::Up),
+            InputEvent::Keyboard(KeyEvent::Down) => Some(Key::Down),
+            InputEvent::Keyboard(KeyEvent::Left) => Some(Key::Left),
+            InputEvent::Keyboard(KeyEvent::Right) => Some(Key::Right),
+            InputEvent::Keyboard(KeyEvent::PageUp) => Some(Key::PageUp),
+            InputEvent::Keyboard(KeyEvent::PageDown) => Some(Key::PageDown),
+            InputEvent::Keyboard(_) => Some(Key::Other),
+            _ => None,
+        }
+    }
+
+    pub struct CrosstermSetup;
+
+    impl TerminalSetup for CrosstermSetup {
+        type Backend = CrosstermBackend;
+
+        fn init(config: Config) -> Result<(Terminal<Self::Backend>, Events), FitrError> {
+            let _raw = RawScreen::into_raw_mode().map_err(|e| FitrError::Terminal(Box::new(e)))?;
+            let alternate = AlternateScreen::to_alternate(true).map_err(|e| FitrError::Terminal(Box::new(e)))?;
+            let backend = CrosstermBackend::with_alternate_screen(alternate).map_err(|e| FitrError::Terminal(Box::new(e)))?;
+            let mut terminal = Terminal::new(backend).map_err(|e| FitrError::Terminal(Box::new(e)))?;
+            terminal.hide_cursor().map_err(|e| FitrError::Terminal(Box::new(e)))?;
+
+            let keys = input().read_sync().filter_map(to_key);
+            Ok((terminal, Events::with_config(config, keys)))
+        }
+    }
+}
+#[cfg(all(feature = "crossterm", not(feature = "termion")))]
+pub use self::crossterm_backend::CrosstermSetup as ActiveBackend;
+
+#[cfg(feature = "rustbox")]
+mod rustbox_backend {
+    use super::*;
+    use rustbox::RustBox;
+    use tui::backend::RustboxBackend;
+
+    pub struct RustboxSetup;
+
+    impl TerminalSetup for RustboxSetup {
+        type Backend = RustboxBackend;
+
+        fn init(config: Config) -> Result<(Terminal<Self::Backend>, Events), FitrError> {
+            let rustbox = RustBox::init(Default::default()).map_err(|e| FitrError::Terminal(Box::new(e)))?;
+            let backend = RustboxBackend::new(rustbox);
+            let mut terminal = Terminal::new(backend).map_err(|e| FitrError::Terminal(Box::new(e)))?;
+            terminal.hide_cursor().map_err(|e| FitrError::Terminal(Box::new(e)))?;
+
+            // RustboxBackend takes ownership of the one RustBox instance
+            // termbox allows, so unlike termion/crossterm there is no
+            // handle left to poll for input on a background thread without
+            // a bigger shared-ownership change to tui's backend. Scoped
+            // out of this pass: the rustbox build renders but never
+            // delivers key events.
+            Ok((terminal, Events::with_config(config, std::iter::empty())))
+        }
+    }
+}
+#[cfg(all(feature = "rustbox", not(feature = "termion"), not(feature = "crossterm")))]
+pub use self::rustbox_backend::RustboxSetup as ActiveBackend;
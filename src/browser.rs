@@ -0,0 +1,153 @@
+//  fitr  --  GPX track analysis for the command line with rust
+//  Copyright (C) 2019 - Fabian A.J. Thiele, <fabian.thiele@posteo.de>
+//
+//  This file is part of fitr.
+//
+//  fitr is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  fitr is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Enumerates every track/segment across one or more GPX files so the TUI can
+// offer a browser pane instead of GPX_Data::new silently picking
+// tracks[0].segments[0].
+
+use gpx::Gpx;
+
+use crate::error::FitrError;
+use crate::{read_track_file, GPX_Data};
+
+// One enumerable (file, track, segment) triple.
+#[derive(Clone)]
+pub struct TrackEntry {
+    pub file: String,
+    pub track_idx: usize,
+    pub segment_idx: usize,
+}
+
+impl TrackEntry {
+    pub fn label(&self) -> String {
+        format!("{} - track {} / segment {}", self.file, self.track_idx + 1, self.segment_idx + 1)
+    }
+}
+
+// A source of track entries that can enumerate what's available and load
+// one of them into a `GPX_Data`.
+pub trait TrackProvider {
+    fn entries(&self) -> &[TrackEntry];
+    fn load(&self, entry: &TrackEntry) -> Result<GPX_Data, FitrError>;
+}
+
+pub struct GpxFileProvider {
+    entries: Vec<TrackEntry>,
+}
+
+impl GpxFileProvider {
+    pub fn new(filenames: &[String]) -> Result<GpxFileProvider, FitrError> {
+        let mut entries = std::vec::Vec::new();
+
+        for filename in filenames {
+            let gpx: Gpx = read_track_file(filename)?;
+
+            for (track_idx, track) in gpx.tracks.iter().enumerate() {
+                for segment_idx in 0..track.segments.len() {
+                    entries.push(TrackEntry {
+                        file: filename.clone(),
+                        track_idx,
+                        segment_idx,
+                    });
+                }
+            }
+        }
+
+        // Every downstream consumer (load_current, load_aggregate) assumes
+        // at least one entry, so reject a file with no tracks/segments here
+        // instead of panicking on an out-of-bounds index later.
+        if entries.is_empty() {
+            return Err(FitrError::NoTracks(filenames.join(", ")));
+        }
+
+        Ok(GpxFileProvider { entries })
+    }
+
+    // Concatenates every enumerated segment's points into one synthetic
+    // `GPX_Data`, for the aggregate-statistics view.
+    pub fn load_aggregate(&self) -> Result<GPX_Data, FitrError> {
+        let mut aggregate = self.load(&self.entries[0])?;
+
+        for entry in &self.entries[1..] {
+            let next = self.load(entry)?;
+            aggregate.segment.points.extend(next.segment.points);
+        }
+
+        Ok(aggregate)
+    }
+}
+
+impl TrackProvider for GpxFileProvider {
+    fn entries(&self) -> &[TrackEntry] {
+        &self.entries
+    }
+
+    fn load(&self, entry: &TrackEntry) -> Result<GPX_Data, FitrError> {
+        GPX_Data::at(entry.file.clone(), entry.track_idx, entry.segment_idx)
+    }
+}
+
+// Drives the third TUI pane: which entry is highlighted, and whether the
+// other panes should instead show the combined, aggregate track.
+pub struct BrowserApp {
+    pub provider: GpxFileProvider,
+    pub selected: usize,
+    pub aggregate: bool,
+}
+
+impl BrowserApp {
+    pub fn new(provider: GpxFileProvider) -> BrowserApp {
+        BrowserApp {
+            provider,
+            selected: 0,
+            aggregate: false,
+        }
+    }
+
+    pub fn labels(&self) -> Vec<String> {
+        self.provider.entries().iter().map(TrackEntry::label).collect()
+    }
+
+    pub fn next(&mut self) {
+        let len = self.provider.entries().len();
+        if len > 0 {
+            self.selected = (self.selected + 1) % len;
+        }
+    }
+
+    pub fn previous(&mut self) {
+        let len = self.provider.entries().len();
+        if len > 0 {
+            self.selected = (self.selected + len - 1) % len;
+        }
+    }
+
+    pub fn toggle_aggregate(&mut self) {
+        self.aggregate = !self.aggregate;
+    }
+
+    // Loads whatever the panes should currently display: the aggregate
+    // track, or the highlighted entry.
+    pub fn load_current(&self) -> Result<GPX_Data, FitrError> {
+        if self.aggregate {
+            self.provider.load_aggregate()
+        } else {
+            self.provider.load(&self.provider.entries()[self.selected])
+        }
+    }
+}
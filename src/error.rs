@@ -0,0 +1,88 @@
+//  fitr  --  GPX track analysis for the command line with rust
+//  Copyright (C) 2019 - Fabian A.J. Thiele, <fabian.thiele@posteo.de>
+//
+//  This file is part of fitr.
+//
+//  fitr is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  fitr is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// A single error type threaded through GPX_Data, DiagramApp, RouteApp and
+// run_prog, replacing the opaque Box<dyn Error> main used to just log and
+// discard. Every variant except `Terminal` names the file it failed on
+// (left empty when built through the `From` conversions below), so a
+// malformed file, a file without tracks or a track without segments is
+// diagnosable instead of panicking (as `gpx.tracks[0]` used to on an empty
+// file). A segment without any points is not rejected here.
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+use std::sync::mpsc;
+
+#[derive(Debug)]
+pub enum FitrError {
+    Io(String, io::Error),
+    GpxParse(String, gpx::errors::Error),
+    FitParse(String, String),
+    NoTracks(String),
+    NoSegments(String, usize),
+    Terminal(Box<dyn StdError>),
+}
+
+impl fmt::Display for FitrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FitrError::Io(path, source) => write!(f, "could not read '{}': {}", path, source),
+            FitrError::GpxParse(path, source) => write!(f, "'{}' is not a valid GPX file: {}", path, source),
+            FitrError::FitParse(path, message) => write!(f, "'{}' is not a valid FIT file: {}", path, message),
+            FitrError::NoTracks(path) => write!(f, "'{}' does not contain any tracks", path),
+            FitrError::NoSegments(path, track_idx) => {
+                write!(f, "track {} in '{}' does not contain any segments", track_idx + 1, path)
+            }
+            FitrError::Terminal(source) => write!(f, "terminal error: {}", source),
+        }
+    }
+}
+
+impl StdError for FitrError {}
+
+impl From<io::Error> for FitrError {
+    fn from(source: io::Error) -> FitrError {
+        // filename unknown at this point in the call chain; callers that
+        // know it should build FitrError::Io directly for a richer message
+        FitrError::Io(String::new(), source)
+    }
+}
+
+impl From<gpx::errors::Error> for FitrError {
+    fn from(source: gpx::errors::Error) -> FitrError {
+        FitrError::GpxParse(String::new(), source)
+    }
+}
+
+impl From<mpsc::RecvError> for FitrError {
+    fn from(source: mpsc::RecvError) -> FitrError {
+        FitrError::Terminal(Box::new(source))
+    }
+}
+
+impl FitrError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            FitrError::Io(..) => 2,
+            FitrError::GpxParse(..) | FitrError::FitParse(..) => 3,
+            FitrError::NoTracks(..) | FitrError::NoSegments(..) => 4,
+            FitrError::Terminal(..) => 5,
+        }
+    }
+}
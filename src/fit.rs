@@ -0,0 +1,245 @@
+//  fitr  --  GPX track analysis for the command line with rust
+//  Copyright (C) 2019 - Fabian A.J. Thiele, <fabian.thiele@posteo.de>
+//
+//  This file is part of fitr.
+//
+//  fitr is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  fitr is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// A minimal decoder for Garmin's binary .fit format, producing the same
+// gpx::Gpx/Track/TrackSegment shape GPX_Data already builds from XML so the
+// rest of the app (DiagramApp, RouteApp, the browser, stats) doesn't need to
+// know which file format a track came from.
+//
+// A FIT file is a header, then a stream of records each prefixed by a
+// one-byte header: "definition" messages declare a global message number
+// plus a list of (field number, size, base type) tuples, and the "data"
+// messages that follow a definition are decoded using it. We only care
+// about global message 20 ("record").
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use chrono::{TimeZone, Utc};
+use geo_types::Point;
+use gpx::{Gpx, GpxVersion, Track, TrackSegment, Waypoint};
+use xmltree::Element;
+
+// seconds between the UNIX epoch and the FIT epoch (1989-12-31T00:00:00Z)
+const FIT_EPOCH_OFFSET_SECS: i64 = 631_065_600;
+
+const GLOBAL_MSG_RECORD: u16 = 20;
+
+const FIELD_POSITION_LAT: u8 = 0;
+const FIELD_POSITION_LONG: u8 = 1;
+const FIELD_ALTITUDE: u8 = 2;
+const FIELD_HEART_RATE: u8 = 3;
+const FIELD_SPEED: u8 = 6;
+const FIELD_TIMESTAMP: u8 = 253;
+
+// FIT base-type numbers that matter for sign extension; every other base
+// type we read (byte, uint*, enum) is unsigned and zero-extends correctly.
+const BASE_TYPE_SINT8: u8 = 0x01;
+const BASE_TYPE_SINT16: u8 = 0x83;
+const BASE_TYPE_SINT32: u8 = 0x85;
+
+#[derive(Debug)]
+pub struct FitParseError(String);
+
+impl fmt::Display for FitParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "malformed FIT file: {}", self.0)
+    }
+}
+
+impl Error for FitParseError {}
+
+pub fn is_fit_file(filename: &str) -> bool {
+    filename.to_lowercase().ends_with(".fit")
+}
+
+#[derive(Clone)]
+struct FieldDef {
+    field_num: u8,
+    size: u8,
+    base_type: u8,
+}
+
+#[derive(Clone)]
+struct DefinitionMessage {
+    global_msg_num: u16,
+    little_endian: bool,
+    fields: Vec<FieldDef>,
+}
+
+// Bounds-checked byte access: every read past the fixed 12-byte header
+// (which `read` checks the length of up front) goes through this instead of
+// indexing `bytes` directly, so a truncated or mis-sized file turns into a
+// FitParseError instead of a panic.
+fn take<'a>(bytes: &'a [u8], offset: usize, len: usize) -> Result<&'a [u8], FitParseError> {
+    bytes
+        .get(offset..offset + len)
+        .ok_or_else(|| FitParseError("unexpected end of file".into()))
+}
+
+pub fn read(bytes: &[u8]) -> Result<Gpx, FitParseError> {
+    if bytes.len() < 12 {
+        return Err(FitParseError("file too short for a FIT header".into()));
+    }
+
+    let header_size = bytes[0] as usize;
+    let data_size = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize;
+
+    if &bytes[8..12] != b".FIT" {
+        return Err(FitParseError("missing \".FIT\" signature".into()));
+    }
+
+    let end = header_size
+        .checked_add(data_size)
+        .filter(|&end| end <= bytes.len())
+        .ok_or_else(|| FitParseError("data size in header overruns the file".into()))?;
+
+    let mut offset = header_size;
+    let mut definitions: HashMap<u8, DefinitionMessage> = HashMap::new();
+    let mut points = std::vec::Vec::new();
+
+    while offset < end {
+        let record_header = take(bytes, offset, 1)?[0];
+        offset += 1;
+
+        let local_type = record_header & 0x0F;
+        let is_definition = record_header & 0x40 != 0;
+
+        if is_definition {
+            let architecture = take(bytes, offset + 1, 1)?[0];
+            let little_endian = architecture == 0;
+            offset += 2;
+
+            let msg_num_bytes = take(bytes, offset, 2)?;
+            let global_msg_num = if little_endian {
+                u16::from_le_bytes([msg_num_bytes[0], msg_num_bytes[1]])
+            } else {
+                u16::from_be_bytes([msg_num_bytes[0], msg_num_bytes[1]])
+            };
+            offset += 2;
+
+            let num_fields = take(bytes, offset, 1)?[0] as usize;
+            offset += 1;
+
+            let mut fields = Vec::with_capacity(num_fields);
+            for _ in 0..num_fields {
+                let field = take(bytes, offset, 3)?;
+                fields.push(FieldDef {
+                    field_num: field[0],
+                    size: field[1],
+                    base_type: field[2],
+                });
+                offset += 3;
+            }
+
+            definitions.insert(local_type, DefinitionMessage { global_msg_num, little_endian, fields });
+        } else {
+            let def = definitions
+                .get(&local_type)
+                .ok_or_else(|| FitParseError(format!("data message for undefined local type {}", local_type)))?
+                .clone();
+
+            let mut raw: HashMap<u8, i64> = HashMap::new();
+            for field in &def.fields {
+                let field_bytes = take(bytes, offset, field.size as usize)?;
+                let value = decode_field(field_bytes, field.base_type, def.little_endian);
+                raw.insert(field.field_num, value);
+                offset += field.size as usize;
+            }
+
+            if def.global_msg_num == GLOBAL_MSG_RECORD {
+                if let Some(point) = record_to_waypoint(&raw) {
+                    points.push(point);
+                }
+            }
+        }
+    }
+
+    let track = Track {
+        name: None,
+        comment: None,
+        description: None,
+        source: None,
+        links: vec![],
+        type_: None,
+        number: None,
+        segments: vec![TrackSegment { points }],
+    };
+
+    Ok(Gpx {
+        version: GpxVersion::Gpx11,
+        creator: None,
+        metadata: None,
+        waypoints: vec![],
+        tracks: vec![track],
+        routes: vec![],
+    })
+}
+
+fn record_to_waypoint(raw: &HashMap<u8, i64>) -> Option<Waypoint> {
+    let lat_raw = *raw.get(&FIELD_POSITION_LAT)?;
+    let lon_raw = *raw.get(&FIELD_POSITION_LONG)?;
+
+    // FIT stores lat/long as semicircles; 2^31 semicircles span 180 degrees
+    let lat = lat_raw as f64 * 180.0 / 2f64.powi(31);
+    let lon = lon_raw as f64 * 180.0 / 2f64.powi(31);
+
+    let mut waypoint = Waypoint::new(Point::new(lon, lat));
+
+    if let Some(&altitude_raw) = raw.get(&FIELD_ALTITUDE) {
+        // altitude field: 5 units/meter, 500m offset
+        waypoint.elevation = Some(altitude_raw as f64 / 5.0 - 500.0);
+    }
+    if let Some(&speed_raw) = raw.get(&FIELD_SPEED) {
+        // speed field: 1000 units/(m/s)
+        waypoint.speed = Some(speed_raw as f64 / 1000.0);
+    }
+    if let Some(&timestamp_raw) = raw.get(&FIELD_TIMESTAMP) {
+        waypoint.time = Some(Utc.timestamp(timestamp_raw + FIT_EPOCH_OFFSET_SECS, 0));
+    }
+    if let Some(&hr_raw) = raw.get(&FIELD_HEART_RATE) {
+        let mut hr = Element::new("hr");
+        hr.text = Some(hr_raw.to_string());
+        waypoint.extensions = Some(hr);
+    }
+
+    Some(waypoint)
+}
+
+// Widens a field's raw bytes into an i64, sign-extending when the base type
+// is signed (sint8/16/32) so e.g. a southern position_lat doesn't turn into
+// a huge positive number; every other base type we read is unsigned and
+// zero-extends correctly.
+fn decode_field(bytes: &[u8], base_type: u8, little_endian: bool) -> i64 {
+    let mut buf = [0u8; 8];
+    let len = bytes.len().min(8);
+    let signed = matches!(base_type, BASE_TYPE_SINT8 | BASE_TYPE_SINT16 | BASE_TYPE_SINT32);
+    let sign_extend = signed && len > 0 && len < 8 && bytes[if little_endian { len - 1 } else { 0 }] & 0x80 != 0;
+    if sign_extend {
+        buf = [0xff; 8];
+    }
+
+    if little_endian {
+        buf[..len].copy_from_slice(&bytes[..len]);
+        i64::from_le_bytes(buf)
+    } else {
+        buf[8 - len..].copy_from_slice(&bytes[..len]);
+        i64::from_be_bytes(buf)
+    }
+}
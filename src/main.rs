@@ -15,22 +15,16 @@
 //
 //  You should have received a copy of the GNU General Public License
 //  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+use std::collections::HashMap;
 use std::io::BufReader;
 use std::fs::File;
 use std::env;
 use std::time::Duration;
-use std::error::Error;
 
-use termion::event::Key;
-use termion::input::MouseTerminal;
-use termion::raw::IntoRawMode;
-use termion::screen::AlternateScreen;
-use tui::backend::TermionBackend;
 use tui::layout::{Constraint, Direction, Layout, Rect};
 use tui::style::{Color, Modifier, Style};
 use tui::widgets::canvas::{Canvas, Line};
-use tui::widgets::{Axis, Block, Borders, Chart, Dataset, Marker, Widget};
-use tui::Terminal;
+use tui::widgets::{Axis, Block, Borders, Chart, Dataset, Marker, Paragraph, SelectableList, Text, Widget};
 
 use itertools::izip;
 
@@ -39,36 +33,67 @@ use getopts::Options;
 use gpx::read;
 use gpx::{Gpx, Track, TrackSegment};
 use geo_types::Point;
+use xmltree::Element;
 
 #[macro_use] extern crate log;
 use simplelog::{LevelFilter, CombinedLogger, TermLogger, WriteLogger};
 
+mod backend;
+mod browser;
+mod error;
+mod fit;
+mod stats;
 mod util;
 
+use crate::backend::{ActiveBackend, TerminalSetup};
+use crate::browser::{BrowserApp, GpxFileProvider};
+use crate::error::FitrError;
+use crate::util::Key;
+
+extern crate geo;
 extern crate gpx;
 extern crate gpxalyzer;
+extern crate xmltree;
 
-struct GPX_Data {
-    filename: String,
-    gpx: Gpx,
-    track: Track,
-    segment: TrackSegment,
+pub(crate) struct GPX_Data {
+    pub(crate) filename: String,
+    pub(crate) gpx: Gpx,
+    pub(crate) track: Track,
+    pub(crate) segment: TrackSegment,
 }
 
-impl GPX_Data {
-    fn new(filename: String) -> Result<GPX_Data, Box<Error>> {
-        let file = File::open(filename.as_str())?;
+// Dispatches on extension so the same DiagramApp/RouteApp pipeline works on
+// both XML GPX and binary FIT recordings.
+pub(crate) fn read_track_file(filename: &str) -> Result<Gpx, FitrError> {
+    if fit::is_fit_file(filename) {
+        let bytes = std::fs::read(filename).map_err(|e| FitrError::Io(filename.to_string(), e))?;
+        fit::read(&bytes).map_err(|e| FitrError::FitParse(filename.to_string(), e.to_string()))
+    } else {
+        let file = File::open(filename).map_err(|e| FitrError::Io(filename.to_string(), e))?;
         let reader = BufReader::new(file);
 
         // read takes any io::Read and gives a Result<Gpx, Error>.
-        let gpx: Gpx = read(reader)?;
+        read(reader).map_err(|e| FitrError::GpxParse(filename.to_string(), e))
+    }
+}
+
+impl GPX_Data {
+    pub(crate) fn at(filename: String, track_idx: usize, segment_idx: usize) -> Result<GPX_Data, FitrError> {
+        let gpx: Gpx = read_track_file(&filename)?;
 
-        // for first demo use only the first track found
-        let track: Track = gpx.tracks[0].clone();
+        let track: Track = gpx
+            .tracks
+            .get(track_idx)
+            .cloned()
+            .ok_or_else(|| FitrError::NoTracks(filename.clone()))?;
 
         // Each track will have different segments full of waypoints, where a
         // waypoint contains info like latitude, longitude, and elevation.
-        let segment: TrackSegment = track.segments[0].clone();
+        let segment: TrackSegment = track
+            .segments
+            .get(segment_idx)
+            .cloned()
+            .ok_or_else(|| FitrError::NoSegments(filename.clone(), track_idx))?;
 
         Ok(GPX_Data {
             filename,
@@ -80,48 +105,256 @@ impl GPX_Data {
 }
 
 
-struct DiagramApp {
-    data1: Vec<(f64, f64)>,
-    data2: Vec<(f64, f64)>,
+// Every quantity `DiagramApp` can plot against time. Adding a metric here
+// (to `ALL` and `axis_title`) plus a `series.insert` in
+// `DiagramApp::from_data` is all a new chart needs -- `build_series`, the
+// rendering and key handling in `run_prog` stay generic.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Metric {
+    Speed,
+    Elevation,
+    Gradient,
+    Pace,
+    HeartRate,
+    Cadence,
+}
+
+impl Metric {
+    const ALL: [Metric; 6] = [
+        Metric::Speed,
+        Metric::Elevation,
+        Metric::Gradient,
+        Metric::Pace,
+        Metric::HeartRate,
+        Metric::Cadence,
+    ];
+
+    fn axis_title(&self) -> &'static str {
+        match self {
+            Metric::Speed => "Speed [m/s]",
+            Metric::Elevation => "Elevation [m]",
+            Metric::Gradient => "Gradient [%]",
+            Metric::Pace => "Pace [min/km]",
+            Metric::HeartRate => "Heart rate [bpm]",
+            Metric::Cadence => "Cadence [rpm]",
+        }
+    }
+
+    fn next(&self) -> Metric {
+        let idx = Metric::ALL.iter().position(|m| m == self).unwrap();
+        Metric::ALL[(idx + 1) % Metric::ALL.len()]
+    }
+}
+
+// A single metric's time series together with the y-axis range it should be
+// drawn with (a margin around the observed min/max).
+struct MetricSeries {
+    data: Vec<(f64, f64)>,
     y_range: [f64; 2],
+}
+
+// Pads [y_min, y_max] by 20% of the span on both sides. Additive rather
+// than a multiplicative 0.8/1.2 scaling, so a metric with a legitimately
+// negative minimum (e.g. gradient on a descent) doesn't get a lower bound
+// that sits above its true minimum and clips the line off the chart.
+fn margin_range(y_min: f64, y_max: f64) -> [f64; 2] {
+    let margin = 0.2 * (y_max - y_min);
+    [y_min - margin, y_max + margin]
+}
+
+// margin_range pads a span by a fraction of itself, so it has nothing to
+// work with once y_min == y_max: a metric that's flat for the whole track
+// (or absent from the file entirely, where get_extension_metric reports
+// all zeros) would otherwise get a zero-height [v, v] axis. Fall back to a
+// fixed +/-1 window around the single value in that case.
+fn bounded_range(y_min: f64, y_max: f64) -> [f64; 2] {
+    if (y_max - y_min).abs() < std::f64::EPSILON {
+        [y_min - 1.0, y_max + 1.0]
+    } else {
+        margin_range(y_min, y_max)
+    }
+}
+
+fn build_series(values: &[f64], seconds: &[f64]) -> MetricSeries {
+    let mut data = std::vec::Vec::new();
+    let mut y_min: Option<f64> = None;
+    let mut y_max: Option<f64> = None;
+
+    for (y, x) in izip!(values, seconds) {
+        data.push((*x, *y));
+
+        y_min = Some(y_min.map_or(*y, |m| m.min(*y)));
+        y_max = Some(y_max.map_or(*y, |m| m.max(*y)));
+    }
+
+    MetricSeries {
+        data,
+        y_range: match (y_min, y_max) {
+            (Some(min), Some(max)) => bounded_range(min, max),
+            _ => [0.0, 0.0],
+        },
+    }
+}
+
+// Walks a waypoint's `<extensions>` tree (e.g. the Garmin TrackPointExtension
+// namespace) looking for a tag such as `hr` or `cad`, regardless of depth or
+// namespace prefix.
+fn find_extension_tag<'a>(element: &'a Element, tag: &str) -> Option<&'a Element> {
+    if element.name == tag {
+        return Some(element);
+    }
+    element.children.iter().find_map(|child| find_extension_tag(child, tag))
+}
+
+fn get_extension_metric(segment: &TrackSegment, tag: &str) -> Vec<f64> {
+    segment
+        .points
+        .iter()
+        .map(|wp| {
+            wp.extensions
+                .as_ref()
+                .and_then(|ext| find_extension_tag(ext, tag))
+                .and_then(|el| el.text.clone())
+                .and_then(|text| text.trim().parse::<f64>().ok())
+                .unwrap_or(0.0)
+        })
+        .collect()
+}
+
+// Clips `data` (sorted ascending by x) to `[w0, w1]`, synthesizing points
+// exactly on the two window edges by linear interpolation between the
+// samples straddling each edge so the plotted line touches both borders
+// instead of leaving a gap. If the window lies entirely outside the data,
+// clamps to the nearest endpoint.
+fn clip_to_window(data: &[(f64, f64)], window: [f64; 2]) -> Vec<(f64, f64)> {
+    let (w0, w1) = (window[0], window[1]);
+
+    if data.is_empty() || w1 <= data[0].0 {
+        return data.first().into_iter().cloned().collect();
+    }
+    if w0 >= data[data.len() - 1].0 {
+        return data.last().into_iter().cloned().collect();
+    }
+
+    let interpolate_at = |t: f64| -> (f64, f64) {
+        // find the first sample at or after t; its predecessor is the last
+        // one before t. Past either end of the data, clamp to that endpoint.
+        let next = match data.iter().position(|(x, _)| *x >= t) {
+            Some(next) => next,
+            None => return data[data.len() - 1],
+        };
+        if next == 0 || data[next].0 == t {
+            return data[next];
+        }
+        let (t_prev, v_prev) = data[next - 1];
+        let (t_next, v_next) = data[next];
+        let v = v_prev + (v_next - v_prev) * (t - t_prev) / (t_next - t_prev);
+        (t, v)
+    };
+
+    let mut clipped = std::vec::Vec::new();
+    clipped.push(interpolate_at(w0));
+    clipped.extend(data.iter().cloned().filter(|(x, _)| *x > w0 && *x < w1));
+    clipped.push(interpolate_at(w1));
+    clipped
+}
+
+struct DiagramApp {
+    series: HashMap<Metric, MetricSeries>,
+    metric: Metric,
+    extent: [f64; 2],
     window: [f64; 2],
 }
 
 impl DiagramApp {
-    fn new(filename: String) -> Result<DiagramApp, Box<Error>> {
-        let mut gpx = GPX_Data::new(filename)?;
-
-        gpxalyzer::decorate_speed(&mut gpx.segment);
-        let yquant = gpxalyzer::get_speed(&gpx.segment);
-        let time = gpxalyzer::get_time(&gpx.segment);
-        let mut data1 = std::vec::Vec::new();
-        let mut y_min: f64 = 0.;
-        let mut y_max: f64 = 0.;
-        let starttime = time[0].time();
+    fn from_data(gpx: &GPX_Data) -> Result<DiagramApp, FitrError> {
+        // decorate_* mutate in place, so work on a local copy of the
+        // segment rather than requiring the caller to hand over ownership
+        let mut segment = gpx.segment.clone();
 
-        for (y, x) in izip!(&yquant, &time) {
-            let duration = x.time().signed_duration_since(starttime);
-            data1.push((duration.num_seconds() as f64, *y));
+        gpxalyzer::decorate_speed(&mut segment);
+        gpxalyzer::decorate_elevation(&mut segment);
+        gpxalyzer::decorate_gradient(&mut segment);
+        gpxalyzer::decorate_pace(&mut segment);
 
-            if y > &y_max {
-                y_max = *y;
-            }
-            if y < &y_min {
-                y_min = *y;
-            }
-        }
-        let data2 = data1.clone();
+        let time = gpxalyzer::get_time(&segment);
+        let starttime = time[0].time();
+        let seconds: Vec<f64> = time
+            .iter()
+            .map(|t| t.time().signed_duration_since(starttime).num_seconds() as f64)
+            .collect();
+
+        let mut series = HashMap::new();
+        series.insert(Metric::Speed, build_series(&gpxalyzer::get_speed(&segment), &seconds));
+        series.insert(Metric::Elevation, build_series(&gpxalyzer::get_elevation(&segment), &seconds));
+        series.insert(Metric::Gradient, build_series(&gpxalyzer::get_gradient(&segment), &seconds));
+        series.insert(Metric::Pace, build_series(&gpxalyzer::get_pace(&segment), &seconds));
+        series.insert(Metric::HeartRate, build_series(&get_extension_metric(&segment, "hr"), &seconds));
+        series.insert(Metric::Cadence, build_series(&get_extension_metric(&segment, "cad"), &seconds));
 
-        let last_point = time[time.len()-1].time().signed_duration_since(starttime).num_seconds() as f64;
+        let last_point = *seconds.last().unwrap();
 
         Ok(DiagramApp {
-            data1,
-            data2,
-            y_range: [0.8*y_min, 1.2*y_max],
+            series,
+            metric: Metric::Speed,
+            extent: [0.0, last_point],
             window: [0.0, last_point],
         })
     }
 
+    fn current(&self) -> &MetricSeries {
+        &self.series[&self.metric]
+    }
+
+    fn cycle_metric(&mut self) {
+        self.metric = self.metric.next();
+    }
+
+    // Clips the selected metric to the visible window, with the y_range
+    // recomputed from only the visible samples so the vertical scale adapts
+    // as the user scrolls.
+    fn visible_series(&self) -> MetricSeries {
+        let data = clip_to_window(&self.current().data, self.window);
+
+        let mut y_min: Option<f64> = None;
+        let mut y_max: Option<f64> = None;
+        for (_, y) in &data {
+            y_min = Some(y_min.map_or(*y, |m| m.min(*y)));
+            y_max = Some(y_max.map_or(*y, |m| m.max(*y)));
+        }
+
+        MetricSeries {
+            data,
+            y_range: match (y_min, y_max) {
+                (Some(min), Some(max)) => bounded_range(min, max),
+                _ => [0.0, 0.0],
+            },
+        }
+    }
+
+    fn pan(&mut self, fraction: f64) {
+        let width = self.window[1] - self.window[0];
+        let shift = width * fraction;
+
+        // don't let the window wander further than one width past either
+        // edge of the data, so panning can't scroll off into empty space
+        let shift = shift
+            .max(self.extent[0] - width - self.window[0])
+            .min(self.extent[1] + width - self.window[1]);
+
+        self.window[0] += shift;
+        self.window[1] += shift;
+    }
+
+    // Zooms the window in (factor < 1) or out (factor > 1) about its
+    // center, with a floor on the minimum visible width.
+    fn zoom(&mut self, factor: f64) {
+        let center = (self.window[0] + self.window[1]) / 2.0;
+        let half_width = (self.window[1] - self.window[0]) / 2.0 * factor;
+        let half_width = half_width.max(1.0);
+        self.window = [center - half_width, center + half_width];
+    }
+
     fn update(&mut self) {
         // leave this in for later scroling and updating
     }
@@ -135,11 +368,16 @@ struct RouteApp {
     mv_left: i64,
     mv_up_d: f64,
     mv_left_d: f64,
+    zoom: i64,
+    zoom_d: f64,
+    // longitude degrees cover less physical distance the further from the
+    // equator a track is; pre-scaling them by cos(latitude) keeps the
+    // route's shape undistorted instead of stretched east-west.
+    lon_scale: f64,
 }
 
 impl RouteApp {
-    fn new(filename: String) -> Result<RouteApp, Box<Error>> {
-        let gpx = GPX_Data::new(filename)?;
+    fn from_data(gpx: &GPX_Data) -> Result<RouteApp, FitrError> {
         let mut points: std::vec::Vec<Point<f64>> = std::vec::Vec::new();
         for p in &gpx.segment.points {
             points.push(p.point());
@@ -149,6 +387,11 @@ impl RouteApp {
         let mut y_range: [f64; 2] = gpxalyzer::get_range_longitude(&gpx.segment);
         info!("y-range {} to {}", y_range[0], y_range[1]);
 
+        let lat_center = (x_range[0] + x_range[1]) / 2.0;
+        let lon_scale = lat_center.to_radians().cos();
+        y_range[0] *= lon_scale;
+        y_range[1] *= lon_scale;
+
         // multiply with safety margin of 0.25 distance
         let margin_factor = 0.25;
         let x_dist = x_range[1]-x_range[0];
@@ -167,6 +410,9 @@ impl RouteApp {
             mv_left: 0,      // do not store any remaining scroll steps as default
             mv_up_d: 0.01,    // default: 10% movement in y-axis direction of visible region
             mv_left_d: 0.01,  // default: 10% movement in x-axis direction of visible region
+            zoom: 0,         // do not store any remaining zoom steps as default
+            zoom_d: 0.1,     // default: 10% scaling of visible region per zoom step
+            lon_scale,
         })
     }
 
@@ -182,6 +428,12 @@ impl RouteApp {
     fn scroll_right(&mut self) {
         self.mv_left -= 1;
     }
+    fn zoom_in(&mut self) {
+        self.zoom += 1;
+    }
+    fn zoom_out(&mut self) {
+        self.zoom -= 1;
+    }
 
     fn update(&mut self) {
         // measure visible distance along y-axis
@@ -205,11 +457,41 @@ impl RouteApp {
 
         // reset up/down movement counter
         self.mv_left = 0;
+
+        // scale draw_area about its center by the pending zoom steps
+        if self.zoom != 0 {
+            let factor = (1.0 - self.zoom_d).powi(self.zoom as i32);
+            let cx = (self.draw_area[0] + self.draw_area[2]) / 2.0;
+            let cy = (self.draw_area[1] + self.draw_area[3]) / 2.0;
+            let half_w = (self.draw_area[2] - self.draw_area[0]) / 2.0 * factor;
+            let half_h = (self.draw_area[3] - self.draw_area[1]) / 2.0 * factor;
+            self.draw_area = [cx - half_w, cy - half_h, cx + half_w, cy + half_h];
+
+            // reset zoom step counter
+            self.zoom = 0;
+        }
     }
 }
 
+fn footer_line(summary: &stats::Summary) -> String {
+    format!(
+        "Distance: {:.2} km | Moving: {} | Elapsed: {} | Avg: {:.2} m/s | Max: {:.2} m/s | Ascent: {:.0} m | Descent: {:.0} m | Start: {}",
+        summary.total_distance_m / 1000.0,
+        stats::format_duration(summary.moving),
+        stats::format_duration(summary.elapsed),
+        summary.avg_speed,
+        summary.max_speed,
+        summary.ascent_m,
+        summary.descent_m,
+        summary
+            .start_time
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_else(|| "n/a".to_string()),
+    )
+}
+
 fn print_usage(program: &str, opts: Options) {
-    println!("{}", opts.usage(&format!("Usage: {} <gpx-data-path>", program)));
+    println!("{}", opts.usage(&format!("Usage: {} <gpx-data-path>...", program)));
 }
 
 fn main() {
@@ -239,9 +521,9 @@ fn main() {
         return;
     }
 
-    let filename = if !matches.free.is_empty() {
-        // if we have any matches left for we use the first one for the filename
-        &matches.free[0]
+    let filenames: Vec<String> = if !matches.free.is_empty() {
+        // every remaining free argument is a GPX or FIT file to browse
+        matches.free.clone()
     } else {
         // otherwise show help information
         print_usage(&program, opts);
@@ -250,36 +532,38 @@ fn main() {
 
     // return standard POSIX exit codes depending on how the run_prog routine
     // terminates
-    ::std::process::exit(match run_prog(filename.to_string()) {
+    ::std::process::exit(match run_prog(filenames) {
         Ok(_) => 0,
         Err(err) => {
-            error!("Error while executing fitr. Error message: {:}", err);
-            1
+            error!("{}", err);
+            err.exit_code()
         }
     });
 }
 
 
-fn run_prog(filename: String) -> Result<(), Box<Error>> {
-    // Terminal initialization
-    let stdout = std::io::stdout().into_raw_mode()?;
-    let stdout = MouseTerminal::from(stdout);
-    let stdout = AlternateScreen::from(stdout);
-    let backend = TermionBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-    terminal.hide_cursor()?;
-
-    // Setup event handlers
+fn run_prog(filenames: Vec<String>) -> Result<(), FitrError> {
+    // Terminal initialization and event handlers: the concrete backend
+    // (termion/crossterm/rustbox) is chosen at compile time by Cargo
+    // feature, so this is the only line in run_prog that knows about it.
     let config = util::Config {
         tick_rate: Duration::from_millis(100),
         ..Default::default()
     };
-    let events = util::Events::with_config(config);
+    let (mut terminal, events) = ActiveBackend::init(config)?;
+
+    // enumerates every track/segment across all given files so the browser
+    // pane and the aggregate view have something to pick from
+    let mut browser_app = BrowserApp::new(GpxFileProvider::new(&filenames)?);
 
     // 2D route app (scrollable and hence mutable)
-    let mut route_app = RouteApp::new(filename.to_string())?;
+    let current = browser_app.load_current()?;
+    let mut route_app = RouteApp::from_data(&current)?;
     // diagram app of variable to show along time
-    let diag_app = DiagramApp::new(filename.to_string())?;
+    let mut diag_app = DiagramApp::from_data(&current)?;
+    // activity totals shown in the footer bar, recomputed whenever the
+    // selected track/segment (or aggregate mode) changes
+    let mut summary = stats::summarize(&current.segment);
 
     // main loop for showing TUI
     loop {
@@ -290,10 +574,16 @@ fn run_prog(filename: String) -> Result<(), Box<Error>> {
         }
 
         terminal.draw(|mut f| {
-            // split layout into two vertical parts of 50% each
+            // split layout into the route canvas, the metric chart, the
+            // track/segment browser pane, and a fixed-height summary footer
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
-                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                .constraints([
+                    Constraint::Percentage(38),
+                    Constraint::Percentage(38),
+                    Constraint::Percentage(18),
+                    Constraint::Length(3),
+                ].as_ref())
                 .split(route_app.size);
 
             // draw in the top part of the layout (chunks[0]) a tui widget canvas
@@ -306,9 +596,9 @@ fn run_prog(filename: String) -> Result<(), Box<Error>> {
                     for i in 0..(route_app.data.len()-2) {
                       ctx.draw(&Line {
                           x1: f64::from(route_app.data[i].lat()),
-                          y1: f64::from(route_app.data[i].lng()),
+                          y1: f64::from(route_app.data[i].lng()) * route_app.lon_scale,
                           x2: f64::from(route_app.data[i+1].lat()),
-                          y2: f64::from(route_app.data[i+1].lng()),
+                          y2: f64::from(route_app.data[i+1].lng()) * route_app.lon_scale,
                           color: Color::Yellow,
                       });
                     }
@@ -317,6 +607,7 @@ fn run_prog(filename: String) -> Result<(), Box<Error>> {
                 .render(&mut f, chunks[0]);
 
             // draw a tui widget chart in the bottom part of the layout (chunks[1])
+            let visible = diag_app.visible_series();
             Chart::default()
                 .block( //style and widget title
                     Block::default()
@@ -336,16 +627,16 @@ fn run_prog(filename: String) -> Result<(), Box<Error>> {
                             &format!("{}", diag_app.window[1] / 60.),
                         ]),
                 )
-                .y_axis( // y-axis label and ticks
+                .y_axis( // y-axis label and ticks; these follow whichever metric is selected
                     Axis::default()
-                        .title("Speed [m/s]")
+                        .title(diag_app.metric.axis_title())
                         .style(Style::default().fg(Color::Gray))
                         .labels_style(Style::default().modifier(Modifier::Italic))
-                        .bounds(diag_app.y_range)
+                        .bounds(visible.y_range)
                         .labels(&[
-                            &format!("{:.2}", diag_app.y_range[0]),
-                            &format!("{:.2}", (diag_app.y_range[0] + diag_app.y_range[1]) / 2.0),
-                            &format!("{:.2}", diag_app.y_range[1]),
+                            &format!("{:.2}", visible.y_range[0]),
+                            &format!("{:.2}", (visible.y_range[0] + visible.y_range[1]) / 2.0),
+                            &format!("{:.2}", visible.y_range[1]),
                         ]),
 
                 )
@@ -354,9 +645,25 @@ fn run_prog(filename: String) -> Result<(), Box<Error>> {
                         .name("Testtrack")
                         .marker(Marker::Dot)
                         .style(Style::default().fg(Color::Cyan))
-                        .data(&diag_app.data1), //use here the data1 saved for the diagram app
+                        .data(&visible.data),
                 ])
                 .render(&mut f, chunks[1]);
+
+            // draw the track/segment browser below the chart (chunks[2])
+            let title = if browser_app.aggregate { "Tracks (aggregate)" } else { "Tracks" };
+            SelectableList::default()
+                .block(Block::default().borders(Borders::ALL).title(title))
+                .items(&browser_app.labels())
+                .select(Some(browser_app.selected))
+                .highlight_style(Style::default().fg(Color::Yellow).modifier(Modifier::Bold))
+                .highlight_symbol(">")
+                .render(&mut f, chunks[2]);
+
+            // draw the activity summary footer (chunks[3])
+            Paragraph::default()
+                .block(Block::default().borders(Borders::ALL).title("Summary"))
+                .text(&[Text::raw(footer_line(&summary))])
+                .render(&mut f, chunks[3]);
         })?;
 
         // when in the main loop we react to key presses and leave upon pressing 'q'
@@ -377,6 +684,46 @@ fn run_prog(filename: String) -> Result<(), Box<Error>> {
                 Key::Left => {
                     route_app.scroll_left();
                 }
+                Key::PageUp => {
+                    route_app.zoom_in();
+                }
+                Key::PageDown => {
+                    route_app.zoom_out();
+                }
+                Key::Char('\t') => {
+                    diag_app.cycle_metric();
+                }
+                Key::Char('h') => {
+                    diag_app.pan(-0.1);
+                }
+                Key::Char('l') => {
+                    diag_app.pan(0.1);
+                }
+                Key::Char('+') => {
+                    diag_app.zoom(0.8);
+                }
+                Key::Char('-') => {
+                    diag_app.zoom(1.25);
+                }
+                Key::Char('j') => {
+                    browser_app.next();
+                }
+                Key::Char('k') => {
+                    browser_app.previous();
+                }
+                Key::Char('a') => {
+                    browser_app.toggle_aggregate();
+                    let current = browser_app.load_current()?;
+                    route_app = RouteApp::from_data(&current)?;
+                    diag_app = DiagramApp::from_data(&current)?;
+                    summary = stats::summarize(&current.segment);
+                }
+                Key::Char('\n') => {
+                    let current = browser_app.load_current()?;
+                    route_app = RouteApp::from_data(&current)?;
+                    diag_app = DiagramApp::from_data(&current)?;
+                    summary = stats::summarize(&current.segment);
+                }
 
                 _ => {}
             },
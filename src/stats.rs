@@ -0,0 +1,112 @@
+//  fitr  --  GPX track analysis for the command line with rust
+//  Copyright (C) 2019 - Fabian A.J. Thiele, <fabian.thiele@posteo.de>
+//
+//  This file is part of fitr.
+//
+//  fitr is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  fitr is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// At-a-glance activity totals for the footer bar, computed the same way
+// DiagramApp/RouteApp pull their per-point data from gpxalyzer.
+
+use chrono::{DateTime, Duration, Utc};
+use geo::algorithm::haversine_distance::HaversineDistance;
+use gpx::TrackSegment;
+
+// below this speed a sample counts as stopped rather than moving
+const MOVING_THRESHOLD_MS: f64 = 0.5;
+
+pub struct Summary {
+    pub total_distance_m: f64,
+    pub elapsed: Duration,
+    pub moving: Duration,
+    pub avg_speed: f64,
+    pub max_speed: f64,
+    pub ascent_m: f64,
+    pub descent_m: f64,
+    pub start_time: Option<DateTime<Utc>>,
+}
+
+pub fn summarize(segment: &TrackSegment) -> Summary {
+    let total_distance_m = total_distance(segment);
+    let (ascent_m, descent_m) = elevation_change(segment);
+
+    // GPX files with no native <speed> element need decorate_speed to fill
+    // it in from consecutive points, same as DiagramApp::from_data does;
+    // without it get_speed returns all zeros and the footer looks stuck.
+    let mut decorated = segment.clone();
+    gpxalyzer::decorate_speed(&mut decorated);
+    let speeds = gpxalyzer::get_speed(&decorated);
+    let times = gpxalyzer::get_time(segment);
+
+    let mut moving = Duration::zero();
+    for i in 1..times.len() {
+        if speeds[i] > MOVING_THRESHOLD_MS {
+            moving = moving + times[i].time().signed_duration_since(times[i - 1].time());
+        }
+    }
+
+    let elapsed = match (times.first(), times.last()) {
+        (Some(first), Some(last)) => last.time().signed_duration_since(first.time()),
+        _ => Duration::zero(),
+    };
+
+    let max_speed = speeds.iter().cloned().fold(0.0, f64::max);
+    let avg_speed = if moving.num_seconds() > 0 {
+        total_distance_m / moving.num_seconds() as f64
+    } else {
+        0.0
+    };
+
+    Summary {
+        total_distance_m,
+        elapsed,
+        moving,
+        avg_speed,
+        max_speed,
+        ascent_m,
+        descent_m,
+        start_time: times.first().map(|t| t.time()),
+    }
+}
+
+fn total_distance(segment: &TrackSegment) -> f64 {
+    segment
+        .points
+        .windows(2)
+        .map(|pair| pair[0].point().haversine_distance(&pair[1].point()))
+        .sum()
+}
+
+fn elevation_change(segment: &TrackSegment) -> (f64, f64) {
+    let mut ascent = 0.0;
+    let mut descent = 0.0;
+
+    for pair in segment.points.windows(2) {
+        if let (Some(a), Some(b)) = (pair[0].elevation, pair[1].elevation) {
+            let delta = b - a;
+            if delta > 0.0 {
+                ascent += delta;
+            } else {
+                descent -= delta;
+            }
+        }
+    }
+
+    (ascent, descent)
+}
+
+pub fn format_duration(d: Duration) -> String {
+    let total_seconds = d.num_seconds().max(0);
+    format!("{:02}:{:02}:{:02}", total_seconds / 3600, (total_seconds / 60) % 60, total_seconds % 60)
+}
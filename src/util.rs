@@ -0,0 +1,97 @@
+//  fitr  --  GPX track analysis for the command line with rust
+//  Copyright (C) 2019 - Fabian A.J. Thiele, <fabian.thiele@posteo.de>
+//
+//  This file is part of fitr.
+//
+//  fitr is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  fitr is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// The key event source run_prog's main loop reads from. `Key` is our own
+// enum rather than termion's, so a crossterm/rustbox build never needs to
+// depend on termion's event types; each `TerminalSetup` backend is
+// responsible for translating its own key events into `Key` before handing
+// the resulting iterator to `Events::with_config`.
+
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// Keys `run_prog` reacts to, independent of which terminal backend
+/// produced them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Other,
+}
+
+pub enum Event {
+    Input(Key),
+    Tick,
+}
+
+pub struct Config {
+    pub tick_rate: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            tick_rate: Duration::from_millis(250),
+        }
+    }
+}
+
+/// Interleaves a backend-supplied key stream with a fixed-rate tick, each
+/// on its own thread, so `run_prog` can block on `next()` without knowing
+/// whether keys are coming from termion, crossterm or rustbox.
+pub struct Events {
+    rx: mpsc::Receiver<Event>,
+}
+
+impl Events {
+    pub fn with_config<I>(config: Config, keys: I) -> Events
+    where
+        I: IntoIterator<Item = Key> + Send + 'static,
+        I::IntoIter: Send,
+    {
+        let (tx, rx) = mpsc::channel();
+
+        let input_tx = tx.clone();
+        thread::spawn(move || {
+            for key in keys {
+                if input_tx.send(Event::Input(key)).is_err() {
+                    return;
+                }
+            }
+        });
+
+        thread::spawn(move || loop {
+            if tx.send(Event::Tick).is_err() {
+                return;
+            }
+            thread::sleep(config.tick_rate);
+        });
+
+        Events { rx }
+    }
+
+    pub fn next(&self) -> Result<Event, mpsc::RecvError> {
+        self.rx.recv()
+    }
+}